@@ -0,0 +1,120 @@
+use crate::errors::PDFError;
+use crate::invoice_parser;
+use crate::models::{ErrorResponse, ExtractOptions, SuccessResponse};
+use crate::pdf_worker::{EmbeddedFilesExtractor, PDFA3Validator};
+
+/// Main business logic for eRechnung processing
+pub struct ERechnungService;
+
+impl ERechnungService {
+    /// Process a PDF file and extract XML content. When `parse` is true, the
+    /// conformance profile and core invoice fields are also parsed out of the XML.
+    /// `options` carries caller-supplied filters (required profile, strict PDF/A-3
+    /// validation, whether to report every embedded file or just the selected XML).
+    pub fn process_pdf(
+        pdf_bytes: Vec<u8>,
+        parse: bool,
+        options: ExtractOptions,
+    ) -> Result<SuccessResponse, ErrorResponse> {
+        // Basic PDF validation
+        if pdf_bytes.len() < 5 || &pdf_bytes[0..5] != b"%PDF-" {
+            return Err(ErrorResponse {
+                file_status: PDFError::InvalidPDF.to_string(),
+                embedded_files: None,
+            });
+        }
+
+        if options.strict_pdfa3 {
+            PDFA3Validator::validate(&pdf_bytes).map_err(|error| ErrorResponse {
+                file_status: error.to_string(),
+                embedded_files: None,
+            })?;
+        }
+
+        // Walk the embedded-files name tree and decode every filespec stream, falling
+        // back to a brute-force object scan if the container's xref/trailer is broken
+        let (embedded_files, recovered) = EmbeddedFilesExtractor::find_embedded_files(&pdf_bytes).map_err(|error| {
+            ErrorResponse {
+                file_status: error.to_string(),
+                embedded_files: None,
+            }
+        })?;
+
+        if embedded_files.is_empty() {
+            return Err(ErrorResponse {
+                file_status: PDFError::NoXMLFile.to_string(),
+                embedded_files: None,
+            });
+        }
+
+        let file_names: Vec<&str> = embedded_files.iter().map(|(name, _)| name.as_str()).collect();
+
+        // Find XML file
+        let (xml_file, xml_bytes) = embedded_files
+            .iter()
+            .find(|(name, _)| name.to_lowercase().ends_with(".xml"))
+            .ok_or_else(|| ErrorResponse {
+                file_status: PDFError::NoXMLFile.to_string(),
+                embedded_files: Some(file_names.join(", ")),
+            })?;
+
+        let xml_content = String::from_utf8(xml_bytes.clone()).map_err(|_| ErrorResponse {
+            file_status: PDFError::ExtractionFailed.to_string(),
+            embedded_files: Some(file_names.join(", ")),
+        })?;
+
+        // Parsed eagerly (not just when `parse` is requested) whenever a profile is
+        // required, so we can reject the file before it ever reaches the caller.
+        let parsed_invoice = if parse || options.require_profile.is_some() {
+            invoice_parser::parse_invoice(&xml_content)
+        } else {
+            None
+        };
+
+        if let Some(required_profile) = &options.require_profile {
+            let actual_profile = parsed_invoice.as_ref().map(|data| data.profile.as_str()).unwrap_or("Unknown");
+            if !profiles_match(required_profile, actual_profile) {
+                return Err(ErrorResponse {
+                    file_status: format!(
+                        "Profile '{}' does not satisfy required profile '{}'",
+                        actual_profile, required_profile
+                    ),
+                    embedded_files: Some(file_names.join(", ")),
+                });
+            }
+        }
+
+        // Determine status based on XML filename
+        let is_facturx = xml_file.to_lowercase() == "factur-x.xml";
+        let status = if is_facturx {
+            "Success".to_string()
+        } else {
+            "XML is not Factur-x.xml".to_string()
+        };
+
+        let reported_files: Vec<&str> = if options.return_all_embedded {
+            file_names
+        } else {
+            vec![xml_file.as_str()]
+        };
+
+        Ok(SuccessResponse {
+            file_status: status,
+            embedded_files: reported_files.join(", "),
+            xml_content,
+            xml_filename: xml_file.clone(),
+            recovered,
+            invoice_data: if parse { parsed_invoice } else { None },
+        })
+    }
+}
+
+/// Compare a caller-supplied profile name against the parsed label, ignoring
+/// case and spaces so "EN16931" matches the "EN 16931" label.
+fn profiles_match(required: &str, actual: &str) -> bool {
+    normalize_profile(required) == normalize_profile(actual)
+}
+
+fn normalize_profile(profile: &str) -> String {
+    profile.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+}
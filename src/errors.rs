@@ -14,4 +14,12 @@ pub enum PDFError {
     IOError(#[from] std::io::Error),
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
+    #[error("Failed to download PDF: {0}")]
+    DownloadFailed(String),
+    #[error("Downloaded file exceeds the maximum allowed size")]
+    DownloadTooLarge,
+    #[error("PDF structure is damaged and could not be repaired")]
+    UnrecoverablePDF,
+    #[error("Upload exceeds the maximum allowed size")]
+    UploadTooLarge,
 }
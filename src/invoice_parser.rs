@@ -0,0 +1,78 @@
+use crate::models::InvoiceData;
+use roxmltree::{Document, Node};
+
+/// Guideline URN fragments mapped to the profile labels used throughout Factur-X/ZUGFeRD tooling.
+const PROFILE_CANDIDATES: &[(&str, &str)] = &[
+    ("xrechnung", "XRechnung"),
+    ("minimum", "MINIMUM"),
+    ("basicwl", "BASIC WL"),
+    ("basic", "BASIC"),
+    ("extended", "EXTENDED"),
+    ("en16931", "EN 16931"),
+];
+
+/// Parse the handful of fields most integrations need out of the extracted CII
+/// (`rsm:CrossIndustryInvoice`) or UBL (`ubl:Invoice`) XML. Returns `None` if the
+/// content isn't well-formed XML; individual fields are best-effort and may be `None`.
+pub fn parse_invoice(xml: &str) -> Option<InvoiceData> {
+    let doc = Document::parse(xml).ok()?;
+    let root = doc.root_element();
+
+    let guideline = find_text(root, &["ID"], Some("GuidelineSpecifiedDocumentContextParameter"))
+        .or_else(|| find_text(root, &["CustomizationID", "ProfileID"], None));
+    let profile = guideline
+        .as_deref()
+        .map(classify_profile)
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Some(InvoiceData {
+        profile,
+        invoice_number: find_text(root, &["ID"], Some("ExchangedDocument"))
+            .or_else(|| direct_child_text(root, "ID")),
+        issue_date: find_text(root, &["DateTimeString"], Some("IssueDateTime"))
+            .or_else(|| direct_child_text(root, "IssueDate")),
+        seller_name: find_text(root, &["Name"], Some("SellerTradeParty"))
+            .or_else(|| find_text(root, &["Name", "RegistrationName"], Some("AccountingSupplierParty"))),
+        buyer_name: find_text(root, &["Name"], Some("BuyerTradeParty"))
+            .or_else(|| find_text(root, &["Name", "RegistrationName"], Some("AccountingCustomerParty"))),
+        currency: find_text(root, &["InvoiceCurrencyCode"], None)
+            .or_else(|| direct_child_text(root, "DocumentCurrencyCode")),
+        grand_total: find_text(root, &["GrandTotalAmount"], None)
+            .or_else(|| find_text(root, &["PayableAmount"], Some("LegalMonetaryTotal"))),
+    })
+}
+
+fn classify_profile(guideline: &str) -> String {
+    let lowercase = guideline.to_lowercase();
+    PROFILE_CANDIDATES
+        .iter()
+        .find(|(needle, _)| lowercase.contains(needle))
+        .map(|(_, label)| label.to_string())
+        .unwrap_or_else(|| guideline.to_string())
+}
+
+/// Find the text of the first descendant whose local tag name matches one of `tag_names`,
+/// optionally restricting the search to the subtree rooted at the first `within` ancestor.
+fn find_text(root: Node, tag_names: &[&str], within: Option<&str>) -> Option<String> {
+    let scope = match within {
+        Some(ancestor_tag) => root
+            .descendants()
+            .find(|node| node.is_element() && node.tag_name().name() == ancestor_tag)?,
+        None => root,
+    };
+
+    scope
+        .descendants()
+        .find(|node| node.is_element() && tag_names.contains(&node.tag_name().name()))
+        .and_then(|node| node.text())
+        .map(|text| text.trim().to_string())
+}
+
+/// Find the text of a direct child element with the given local tag name (used for
+/// flat UBL root-level fields like `cbc:ID`/`cbc:IssueDate`).
+fn direct_child_text(root: Node, tag_name: &str) -> Option<String> {
+    root.children()
+        .find(|node| node.is_element() && node.tag_name().name() == tag_name)
+        .and_then(|node| node.text())
+        .map(|text| text.trim().to_string())
+}
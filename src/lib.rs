@@ -3,11 +3,19 @@
 pub mod erechnung_pdf_service;
 pub mod errors;
 pub mod handlers;
+pub mod invoice_parser;
 pub mod models;
 pub mod pdf_worker;
+pub mod test;
 
 // Re-export commonly used items
 pub use erechnung_pdf_service::ERechnungService;
 pub use errors::PDFError;
-pub use handlers::{extract_xml, extract_xml_file, health_check};
-pub use models::{ErrorResponse, SuccessResponse};
+pub use handlers::{
+    extract_xml, extract_xml_batch, extract_xml_file, extract_xml_from_url, health_check, UploadLimits,
+};
+pub use models::{
+    BatchItem, BatchResponse, BatchResult, ErrorResponse, ExtractOptions, InvoiceData, SuccessResponse,
+    UrlExtractRequest,
+};
+pub use test::{create_form_data_payload_and_headers, pdf_upload_request};
@@ -2,11 +2,14 @@
 mod erechnung_pdf_service;
 mod errors;
 mod handlers;
+mod invoice_parser;
 mod models;
 mod pdf_worker;
 
 use actix_web::{middleware, web, App, HttpServer};
-use handlers::{extract_xml, extract_xml_file, health_check};
+use handlers::{
+    extract_xml, extract_xml_batch, extract_xml_file, extract_xml_from_url, health_check, UploadLimits,
+};
 
 // Main Application
 #[actix_web::main]
@@ -16,14 +19,22 @@ async fn main() -> std::io::Result<()> {
     println!("📋 Endpoints:");
     println!("   POST /extract_xml - Extract XML from PDF/A-3 (JSON response)");
     println!("   POST /extract_xml_file - Extract XML from PDF/A-3 (file download)");
+    println!("   POST /extract_xml_url - Extract XML from a PDF fetched by URL");
+    println!("   POST /extract_xml_batch - Extract XML from multiple PDFs in one request");
     println!("   GET  /health - Health check");
 
     HttpServer::new(|| {
+        let limits = UploadLimits::default();
         App::new()
+            .app_data(limits.multipart_form_config())
+            .app_data(web::Data::new(limits))
             .wrap(middleware::Logger::default())
+            .wrap(middleware::Compress::default())
             .route("/health", web::get().to(health_check))
             .route("/extract_xml", web::post().to(extract_xml))
             .route("/extract_xml_file", web::post().to(extract_xml_file))
+            .route("/extract_xml_url", web::post().to(extract_xml_from_url))
+            .route("/extract_xml_batch", web::post().to(extract_xml_batch))
     })
     .bind(("127.0.0.1", 8080))?
     .run()
@@ -8,6 +8,46 @@ pub struct ErrorResponse {
     pub embedded_files: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UrlExtractRequest {
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExtractQuery {
+    #[serde(default)]
+    pub parse: bool,
+    /// Return the extracted XML as a downloadable attachment instead of a JSON body.
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// Caller-supplied flags for `/extract_xml`, carried as the multipart form's
+/// optional JSON `options` field.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Reject the file unless its parsed conformance profile matches (e.g. "EN16931", "XRechnung").
+    pub require_profile: Option<String>,
+    /// Report every embedded file name, not just the selected XML's.
+    #[serde(default)]
+    pub return_all_embedded: bool,
+    /// Reject the file unless it validates as PDF/A-3.
+    #[serde(default)]
+    pub strict_pdfa3: bool,
+}
+
+/// Conformance profile and core fields pulled from the CII/UBL invoice XML.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InvoiceData {
+    pub profile: String,
+    pub invoice_number: Option<String>,
+    pub issue_date: Option<String>,
+    pub seller_name: Option<String>,
+    pub buyer_name: Option<String>,
+    pub currency: Option<String>,
+    pub grand_total: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SuccessResponse {
     #[serde(rename = "file status")]
@@ -18,4 +58,33 @@ pub struct SuccessResponse {
     pub xml_content: String,
     #[serde(rename = "xml_filename")]
     pub xml_filename: String,
+    /// True when the source PDF had a broken xref/trailer and the embedded
+    /// file was salvaged via the brute-force object scan rather than a clean parse.
+    #[serde(rename = "recovered")]
+    pub recovered: bool,
+    #[serde(rename = "invoice_data", skip_serializing_if = "Option::is_none")]
+    pub invoice_data: Option<InvoiceData>,
+}
+
+/// Per-file outcome of `/extract_xml_batch`: either a `SuccessResponse` or an
+/// `ErrorResponse`, tagged with the original filename. `Success` is boxed
+/// since `SuccessResponse` is much larger than `ErrorResponse`, and this enum
+/// is carried in a `Vec` per batch request.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BatchResult {
+    Success(Box<SuccessResponse>),
+    Error(ErrorResponse),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchItem {
+    pub filename: String,
+    #[serde(flatten)]
+    pub result: BatchResult,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchResponse {
+    pub results: Vec<BatchItem>,
 }
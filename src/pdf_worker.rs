@@ -1,13 +1,32 @@
 use crate::errors::PDFError;
-use anyhow::Result;
-use lopdf::Document;
+use flate2::read::ZlibDecoder;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+use std::io::{Cursor, Read};
+
+/// Hard ceiling on any single decompressed stream, so a crafted FlateDecode
+/// "zip bomb" well within the upload size limit can't OOM the process before
+/// we even get to checking whether the payload is XML.
+const MAX_DECOMPRESSED_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Name trees are typically a handful of levels deep; this is generous
+/// headroom while still bailing out of a cyclic `/Kids` chain quickly.
+const MAX_NAME_TREE_DEPTH: usize = 64;
+
+/// Upper bound on how far `iter_objects` scans forward for a matching
+/// `endobj` before giving up on an `obj` keyword. Without this, a crafted
+/// upload with many `N G obj` occurrences and no real `endobj` turns every
+/// lookup into a full scan of the remaining buffer.
+const MAX_OBJECT_BODY_SCAN_BYTES: usize = 8 * 1024 * 1024;
+
+/// An embedded file as `(filename, decoded bytes)`.
+type EmbeddedFile = (String, Vec<u8>);
 
 /// Validates PDF/A-3 format
 pub struct PDFA3Validator;
 
 impl PDFA3Validator {
     pub fn validate(pdf_bytes: &[u8]) -> Result<(), PDFError> {
-
         let pdf_string = String::from_utf8_lossy(pdf_bytes);
         let is_pdfa3 = pdf_string.contains("<pdfaid:part>3</pdfaid:part>");
 
@@ -19,78 +38,300 @@ impl PDFA3Validator {
     }
 }
 
-/// Extracts embedded files from PDF documents
+/// Extracts embedded files from PDF documents by walking the `/EmbeddedFiles`
+/// name tree rather than scanning the raw bytes, so compressed streams,
+/// object streams and cross-reference streams are all handled correctly.
 pub struct EmbeddedFilesExtractor;
 
 impl EmbeddedFilesExtractor {
+    /// Parse the PDF and return every embedded file as `(filename, decoded bytes)`,
+    /// along with whether the container had to be recovered by brute force because
+    /// its cross-reference table or trailer was broken.
+    pub fn find_embedded_files(pdf_bytes: &[u8]) -> Result<(Vec<EmbeddedFile>, bool), PDFError> {
+        match Document::load_from(Cursor::new(pdf_bytes)) {
+            Ok(doc) => Self::extract_from_document(&doc).map(|files| (files, false)),
+            Err(_) => {
+                let recovered = brute_force_recover(pdf_bytes);
+                if recovered.is_empty() {
+                    Err(PDFError::UnrecoverablePDF)
+                } else {
+                    Ok((recovered, true))
+                }
+            }
+        }
+    }
 
-    /// Find all embedded file names in the PDF
-    pub fn find_embedded_files(pdf_bytes: &[u8]) -> Vec<String> {
-        let pdf_string = String::from_utf8_lossy(pdf_bytes);
-        let mut embedded_files = Vec::new();
+    /// Walk the catalog's `/Names /EmbeddedFiles` name tree and decode each filespec's stream.
+    fn extract_from_document(doc: &Document) -> Result<Vec<EmbeddedFile>, PDFError> {
+        let embedded_files_dict = Self::embedded_files_dict(doc)?;
+
+        let mut name_value_pairs = Vec::new();
+        let mut visited = HashSet::new();
+        Self::collect_name_tree(doc, embedded_files_dict, 0, &mut visited, &mut name_value_pairs)?;
 
-        if !pdf_string.contains("/EmbeddedFiles") {
-            return embedded_files;
+        let mut files = Vec::new();
+        for (name, filespec_ref) in name_value_pairs {
+            if let Some(bytes) = Self::decode_filespec(doc, &filespec_ref) {
+                files.push((name, bytes));
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Locate `catalog /Names /EmbeddedFiles`, the root of the embedded-files name tree.
+    fn embedded_files_dict(doc: &Document) -> Result<&Dictionary, PDFError> {
+        let catalog = doc.catalog().map_err(|_| PDFError::ExtractionFailed)?;
+        let names = catalog
+            .get(b"Names")
+            .ok()
+            .and_then(|object| Self::resolve_dict(doc, object))
+            .ok_or(PDFError::NoXMLFile)?;
+        names
+            .get(b"EmbeddedFiles")
+            .ok()
+            .and_then(|object| Self::resolve_dict(doc, object))
+            .ok_or(PDFError::NoXMLFile)
+    }
+
+    /// Recurse through `/Kids` intermediate nodes, collecting every `/Names` leaf
+    /// as alternating `(name, filespec reference)` pairs.
+    ///
+    /// Guards against malformed/crafted trees: `visited` stops a cyclic or
+    /// mutually-referencing `/Kids` chain from recursing forever, and `depth`
+    /// is a backstop against pathologically deep (but acyclic) trees.
+    fn collect_name_tree(
+        doc: &Document,
+        node: &Dictionary,
+        depth: usize,
+        visited: &mut HashSet<ObjectId>,
+        out: &mut Vec<(String, Object)>,
+    ) -> Result<(), PDFError> {
+        if depth > MAX_NAME_TREE_DEPTH {
+            return Err(PDFError::ExtractionFailed);
+        }
+
+        if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+            let mut entries = names.iter();
+            while let (Some(name_obj), Some(value_obj)) = (entries.next(), entries.next()) {
+                if let Object::String(bytes, _) = name_obj {
+                    out.push((String::from_utf8_lossy(bytes).to_string(), value_obj.clone()));
+                }
+            }
         }
 
-        let names_start = pdf_string.find("/Names").unwrap_or(0);
-        let names_section = &pdf_string[names_start..];
-
-        if let Some(array_start) = names_section.find('[') {
-            if let Some(array_end) = names_section[array_start..].find(']') {
-                let names_content = &names_section[array_start + 1..array_start + array_end];
-
-                let mut in_string = false;
-                let mut current_string = String::new();
-
-                for ch in names_content.chars() {
-                    match ch {
-                        '(' => {
-                            in_string = true;
-                            current_string.clear();
-                        }
-                        ')' => {
-                            if in_string && !current_string.is_empty() {
-                                embedded_files.push(current_string.clone());
-                            }
-                            in_string = false;
-                        }
-                        _ if in_string => current_string.push(ch),
-                        _ => {}
+        if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+            for kid in kids {
+                if let Ok(kid_id) = kid.as_reference() {
+                    if !visited.insert(kid_id) {
+                        continue;
+                    }
+                    if let Ok(kid_dict) = doc.get_object(kid_id).and_then(Object::as_dict) {
+                        Self::collect_name_tree(doc, kid_dict, depth + 1, visited, out)?;
                     }
                 }
             }
         }
 
-        embedded_files
+        Ok(())
+    }
+
+    /// Dereference a filespec (`/EF /F`, falling back to `/UF`) and decompress its stream.
+    fn decode_filespec(doc: &Document, filespec_obj: &Object) -> Option<Vec<u8>> {
+        let filespec = Self::resolve_dict(doc, filespec_obj)?;
+        let ef = filespec.get(b"EF").and_then(Object::as_dict).ok()?;
+
+        let stream_ref = ef
+            .get(b"F")
+            .or_else(|_| ef.get(b"UF"))
+            .ok()?;
+        let stream_id: ObjectId = stream_ref.as_reference().ok()?;
+        let stream = doc.get_object(stream_id).and_then(Object::as_stream).ok()?;
+
+        decompress_stream_capped(stream)
+    }
+
+    /// Resolve an object that may be a direct dictionary or a reference to one.
+    fn resolve_dict<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Dictionary> {
+        match object {
+            Object::Dictionary(dict) => Some(dict),
+            Object::Reference(id) => doc.get_object(*id).and_then(Object::as_dict).ok(),
+            _ => None,
+        }
     }
 }
 
-/// Carve out XML content from PDF bytes using lopdf
-pub fn carveout_xml_from_pdf(pdf_bytes: &[u8]) -> Result<Vec<String>> {
-    use std::io::Cursor;
-    let cursor = Cursor::new(pdf_bytes);
-    let doc = Document::load_from(cursor)?;
-
-    let mut xml_contents = Vec::new();
-
-    // Iterate through all objects in the PDF
-    for (_, object) in doc.objects.iter() {
-        if let Ok(stream) = object.as_stream() {
-            // Check if it's XML metadata or embedded files
-            if let Ok(decoded) = stream.decompressed_content() {
-                let text = String::from_utf8_lossy(&decoded);
-                if is_xml_content(&text) {
-                    xml_contents.push(text.to_string());
-                }
+/// Decompress a stream object's content, enforcing `MAX_DECOMPRESSED_STREAM_BYTES`
+/// so a crafted FlateDecode "zip bomb" can't expand unbounded in memory. Streams
+/// declaring `/FlateDecode` are inflated through a capped reader directly; a
+/// stream with no `/Filter` at all (a perfectly legal, common case for an
+/// embedded file stored uncompressed) returns its content as-is, still capped;
+/// any other filter falls back to `lopdf`'s own decoder with a post-hoc size check.
+fn decompress_stream_capped(stream: &lopdf::Stream) -> Option<Vec<u8>> {
+    match stream.dict.get(b"Filter") {
+        Ok(Object::Name(name)) if name == b"FlateDecode" => {
+            read_capped(ZlibDecoder::new(stream.content.as_slice()), MAX_DECOMPRESSED_STREAM_BYTES)
+        }
+        Err(_) => {
+            if stream.content.len() as u64 > MAX_DECOMPRESSED_STREAM_BYTES {
+                None
+            } else {
+                Some(stream.content.clone())
+            }
+        }
+        _ => {
+            let decoded = stream.decompressed_content().ok()?;
+            if decoded.len() as u64 > MAX_DECOMPRESSED_STREAM_BYTES {
+                None
+            } else {
+                Some(decoded)
+            }
+        }
+    }
+}
+
+/// Read at most `cap` bytes from `reader`, returning `None` if more than that
+/// was available (i.e. the decompressed output would have exceeded the cap).
+fn read_capped<R: Read>(reader: R, cap: u64) -> Option<Vec<u8>> {
+    let mut limited = reader.take(cap + 1);
+    let mut buf = Vec::new();
+    limited.read_to_end(&mut buf).ok()?;
+    if buf.len() as u64 > cap {
+        None
+    } else {
+        Some(buf)
+    }
+}
+
+/// Fallback for PDFs whose xref table/trailer is broken and `lopdf` refuses to load
+/// them at all: linearly scan the raw bytes for `N G obj ... endobj` fragments,
+/// find any embedded-file object, and decode its stream.
+fn brute_force_recover(pdf_bytes: &[u8]) -> Vec<EmbeddedFile> {
+    let mut files = Vec::new();
+
+    for (index, (obj_num, obj_body)) in iter_objects(pdf_bytes).into_iter().enumerate() {
+        if !looks_like_embedded_file(obj_body) {
+            continue;
+        }
+
+        if let Some(raw_stream) = extract_stream_body(obj_body) {
+            let content = decompress_stream(obj_body, raw_stream);
+            files.push((format!("recovered-{}-{}.xml", obj_num, index), content));
+        }
+    }
+
+    files
+}
+
+/// Does this object's dictionary look like an embedded-file stream?
+fn looks_like_embedded_file(obj_body: &[u8]) -> bool {
+    contains(obj_body, b"/Type /EmbeddedFile")
+        || contains(obj_body, b"/Type/EmbeddedFile")
+        || contains(obj_body, b"/Subtype /text#2Fxml")
+        || contains(obj_body, b"/Subtype/text#2Fxml")
+        || contains(obj_body, b"/Subtype /application#2Fxml")
+        || contains(obj_body, b"/Subtype/application#2Fxml")
+}
+
+/// Scan `bytes` for `N G obj ... endobj` fragments, returning each object's
+/// number and the slice of bytes between `obj` and `endobj`.
+fn iter_objects(bytes: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut objects = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(rel_pos) = find_subslice(&bytes[search_from..], b"obj") {
+        let keyword_pos = search_from + rel_pos;
+
+        // Skip the tail of "endobj" matching "obj".
+        if keyword_pos >= 3 && &bytes[keyword_pos - 3..keyword_pos] == b"end" {
+            search_from = keyword_pos + 3;
+            continue;
+        }
+
+        let header = parse_object_header(bytes, keyword_pos);
+        let body_start = keyword_pos + 3;
+        let scan_end = bytes.len().min(body_start + MAX_OBJECT_BODY_SCAN_BYTES);
+
+        match (header, find_subslice(&bytes[body_start..scan_end], b"endobj")) {
+            (Some(obj_num), Some(end_rel)) => {
+                let body_end = body_start + end_rel;
+                objects.push((obj_num, &bytes[body_start..body_end]));
+                search_from = body_end + 6;
             }
+            _ => search_from = keyword_pos + 3,
+        }
+    }
+
+    objects
+}
+
+/// Walk backwards from the `obj` keyword over "N G " to recover the object number.
+fn parse_object_header(bytes: &[u8], keyword_pos: usize) -> Option<u32> {
+    let mut i = keyword_pos;
+
+    while i > 0 && bytes[i - 1].is_ascii_whitespace() {
+        i -= 1;
+    }
+    let gen_end = i;
+    while i > 0 && bytes[i - 1].is_ascii_digit() {
+        i -= 1;
+    }
+    if i == gen_end {
+        return None;
+    }
+
+    while i > 0 && bytes[i - 1].is_ascii_whitespace() {
+        i -= 1;
+    }
+    let num_end = i;
+    while i > 0 && bytes[i - 1].is_ascii_digit() {
+        i -= 1;
+    }
+    let num_start = i;
+    if num_start == num_end {
+        return None;
+    }
+
+    std::str::from_utf8(&bytes[num_start..num_end]).ok()?.parse().ok()
+}
+
+/// Extract the raw (possibly still-compressed) bytes between `stream` and `endstream`.
+fn extract_stream_body(obj_body: &[u8]) -> Option<&[u8]> {
+    let stream_kw = find_subslice(obj_body, b"stream")?;
+    let mut start = stream_kw + 6;
+    if obj_body.get(start) == Some(&b'\r') {
+        start += 1;
+    }
+    if obj_body.get(start) == Some(&b'\n') {
+        start += 1;
+    }
+
+    let end_rel = find_subslice(&obj_body[start..], b"endstream")?;
+    let mut end = start + end_rel;
+    while end > start && matches!(obj_body[end - 1], b'\r' | b'\n') {
+        end -= 1;
+    }
+
+    Some(&obj_body[start..end])
+}
+
+/// Apply FlateDecode if the object's dictionary declares it, otherwise return the raw bytes.
+/// The decoded output is capped at `MAX_DECOMPRESSED_STREAM_BYTES` so a zip-bomb-style
+/// stream can't expand unbounded in memory; past the cap we fall back to the raw bytes.
+fn decompress_stream(obj_dict: &[u8], raw: &[u8]) -> Vec<u8> {
+    if contains(obj_dict, b"/FlateDecode") {
+        if let Some(decoded) = read_capped(ZlibDecoder::new(raw), MAX_DECOMPRESSED_STREAM_BYTES) {
+            return decoded;
         }
     }
 
-    Ok(xml_contents)
+    raw.to_vec()
 }
 
-/// Check if the content appears to be XML
-fn is_xml_content(text: &str) -> bool {
-    text.contains("<?xml") 
-}
\ No newline at end of file
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    find_subslice(haystack, needle).is_some()
+}
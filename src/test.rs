@@ -0,0 +1,50 @@
+//! Multipart payload builders for testing this crate's upload endpoints,
+//! mirroring the helper actix-multipart ships in its own `test` module.
+//! Exposed publicly so downstream crates testing against this service don't
+//! have to hand-format `multipart/form-data` boundaries themselves.
+
+use actix_web::http::header::{HeaderMap, CONTENT_TYPE};
+use bytes::Bytes;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+/// Build a single-field `multipart/form-data` payload, plus the headers
+/// needed to submit it, using a random alphanumeric boundary.
+pub fn create_form_data_payload_and_headers(
+    field_name: &str,
+    filename: &str,
+    mime: &str,
+    body: Vec<u8>,
+) -> (Bytes, HeaderMap) {
+    let boundary: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+
+    let mut payload = Vec::new();
+    payload.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    payload.extend_from_slice(
+        format!("Content-Disposition: form-data; name=\"{field_name}\"; filename=\"{filename}\"\r\n")
+            .as_bytes(),
+    );
+    payload.extend_from_slice(format!("Content-Type: {mime}\r\n\r\n").as_bytes());
+    payload.extend_from_slice(&body);
+    payload.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        CONTENT_TYPE,
+        format!("multipart/form-data; boundary={boundary}")
+            .parse()
+            .expect("boundary is alphanumeric, so this is always a valid header value"),
+    );
+
+    (Bytes::from(payload), headers)
+}
+
+/// Convenience wrapper around [`create_form_data_payload_and_headers`] for the
+/// common case: a `file` field carrying a PDF.
+pub fn pdf_upload_request(filename: &str, body: Vec<u8>) -> (Bytes, HeaderMap) {
+    create_form_data_payload_and_headers("file", filename, "application/pdf", body)
+}
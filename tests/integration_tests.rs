@@ -1,7 +1,10 @@
 use actix_web::{test, App, web, http::StatusCode};
 use serde_json::Value;
 use bytes::Bytes;
-use extract_xml_rechnung::{health_check, extract_xml, ErrorResponse, SuccessResponse, PDFError, ERechnungService};
+use extract_xml_rechnung::{
+    health_check, extract_xml, extract_xml_batch, extract_xml_from_url, pdf_upload_request, ErrorResponse,
+    ExtractOptions, SuccessResponse, PDFError, ERechnungService, UploadLimits,
+};
 
 /// Create test application without middleware to avoid type complexity
 fn create_test_app() -> App<
@@ -13,9 +16,98 @@ fn create_test_app() -> App<
         InitError = (),
     >,
 > {
+    let limits = UploadLimits::default();
     App::new()
+        .app_data(limits.multipart_form_config())
+        .app_data(web::Data::new(limits))
         .route("/health", web::get().to(health_check))
         .route("/extract_xml", web::post().to(extract_xml))
+        .route("/extract_xml_url", web::post().to(extract_xml_from_url))
+        .route("/extract_xml_batch", web::post().to(extract_xml_batch))
+}
+
+/// Build a multipart body with several same-named `file` parts sharing one
+/// boundary, for exercising `/extract_xml_batch` (which `pdf_upload_request`,
+/// being single-field, can't produce on its own).
+fn multi_pdf_upload_request(files: &[(&str, &[u8])]) -> (Bytes, String) {
+    let boundary = "----batchtestboundary1234567890";
+    let mut body = Vec::new();
+
+    for (filename, content) in files {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        body.extend_from_slice(
+            format!("Content-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n").as_bytes(),
+        );
+        body.extend_from_slice(b"Content-Type: application/pdf\r\n\r\n");
+        body.extend_from_slice(content);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    (Bytes::from(body), format!("multipart/form-data; boundary={boundary}"))
+}
+
+/// Build a minimal but structurally valid PDF/A-3 whose `/Names
+/// /EmbeddedFiles` name tree holds exactly one XML filespec, for exercising
+/// the success path of the extraction endpoints end to end.
+fn build_valid_pdfa3_with_xml(xml_filename: &str, xml_content: &str) -> Vec<u8> {
+    use lopdf::{Dictionary, Document, Object, Stream, StringFormat};
+
+    let mut doc = Document::with_version("1.7");
+
+    let xml_stream_id = doc.add_object(Stream::new(Dictionary::new(), xml_content.as_bytes().to_vec()));
+
+    let mut ef_dict = Dictionary::new();
+    ef_dict.set("F", Object::Reference(xml_stream_id));
+
+    let mut filespec_dict = Dictionary::new();
+    filespec_dict.set("Type", Object::Name(b"Filespec".to_vec()));
+    filespec_dict.set(
+        "F",
+        Object::String(xml_filename.as_bytes().to_vec(), StringFormat::Literal),
+    );
+    filespec_dict.set("EF", Object::Dictionary(ef_dict));
+    let filespec_id = doc.add_object(filespec_dict);
+
+    let mut embedded_files_dict = Dictionary::new();
+    embedded_files_dict.set(
+        "Names",
+        Object::Array(vec![
+            Object::String(xml_filename.as_bytes().to_vec(), StringFormat::Literal),
+            Object::Reference(filespec_id),
+        ]),
+    );
+
+    let mut names_dict = Dictionary::new();
+    names_dict.set("EmbeddedFiles", Object::Dictionary(embedded_files_dict));
+
+    let mut pages_dict = Dictionary::new();
+    pages_dict.set("Type", Object::Name(b"Pages".to_vec()));
+    pages_dict.set("Kids", Object::Array(vec![]));
+    pages_dict.set("Count", Object::Integer(0));
+    let pages_id = doc.add_object(pages_dict);
+
+    let mut catalog_dict = Dictionary::new();
+    catalog_dict.set("Type", Object::Name(b"Catalog".to_vec()));
+    catalog_dict.set("Pages", Object::Reference(pages_id));
+    catalog_dict.set("Names", Object::Dictionary(names_dict));
+    let catalog_id = doc.add_object(catalog_dict);
+
+    // PDFA3Validator just substring-scans the raw bytes for the XMP marker,
+    // so stashing it in an Info string is enough to pass validation here.
+    let mut info_dict = Dictionary::new();
+    info_dict.set(
+        "Producer",
+        Object::String(b"<pdfaid:part>3</pdfaid:part>".to_vec(), StringFormat::Literal),
+    );
+    let info_id = doc.add_object(info_dict);
+
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+    doc.trailer.set("Info", Object::Reference(info_id));
+
+    let mut buffer = Vec::new();
+    doc.save_to(&mut buffer).expect("in-memory PDF save should not fail");
+    buffer
 }
 
 #[actix_web::test]
@@ -78,15 +170,15 @@ async fn test_extract_xml_invalid_multipart() {
 #[actix_web::test]
 async fn test_extract_xml_with_fake_pdf() {
     let app = test::init_service(create_test_app()).await;
-    
+
     // Create a fake PDF that will fail validation
-    let fake_pdf = create_fake_pdf_multipart();
-    
-    let req = test::TestRequest::post()
-        .uri("/extract_xml")
-        .insert_header(("content-type", "multipart/form-data; boundary=----WebKitFormBoundary7MA4YWxkTrZu0gW"))
-        .set_payload(fake_pdf)
-        .to_request();
+    let (payload, headers) = pdf_upload_request("test.pdf", b"Not a real PDF file".to_vec());
+
+    let mut req = test::TestRequest::post().uri("/extract_xml");
+    for (name, value) in headers.iter() {
+        req = req.insert_header((name.clone(), value.clone()));
+    }
+    let req = req.set_payload(payload).to_request();
     let resp = test::call_service(&app, req).await;
     
     assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
@@ -127,22 +219,103 @@ async fn test_wrong_http_methods() {
     assert!(resp.status() == StatusCode::NOT_FOUND || resp.status() == StatusCode::METHOD_NOT_ALLOWED);
 }
 
-/// Create a fake multipart form data that mimics a PDF upload
-fn create_fake_pdf_multipart() -> Bytes {
-    let boundary = "----WebKitFormBoundary7MA4YWxkTrZu0gW";
-    let fake_pdf_content = b"Not a real PDF file";
-    
-    let multipart_body = format!(
-        "--{boundary}\r\n\
-        Content-Disposition: form-data; name=\"file\"; filename=\"test.pdf\"\r\n\
-        Content-Type: application/pdf\r\n\r\n\
-        {content}\r\n\
-        --{boundary}--\r\n",
-        boundary = boundary,
-        content = std::str::from_utf8(fake_pdf_content).unwrap()
+#[actix_web::test]
+async fn test_health_check_is_gzip_compressed_when_accepted() {
+    let app = test::init_service(
+        App::new()
+            .wrap(actix_web::middleware::Compress::default())
+            .route("/health", web::get().to(health_check)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/health")
+        .insert_header(("accept-encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("content-encoding").and_then(|v| v.to_str().ok()),
+        Some("gzip")
     );
-    
-    Bytes::from(multipart_body)
+}
+
+#[actix_web::test]
+async fn test_extract_xml_url_rejects_loopback_target() {
+    let app = test::init_service(create_test_app()).await;
+
+    let req = test::TestRequest::post()
+        .uri("/extract_xml_url")
+        .set_json(&serde_json::json!({ "url": "http://127.0.0.1/internal" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert!(body.file_status.contains("Failed to download PDF"));
+}
+
+#[actix_web::test]
+async fn test_extract_xml_batch_processes_each_file_independently() {
+    let app = test::init_service(create_test_app()).await;
+
+    let (payload, content_type) = multi_pdf_upload_request(&[
+        ("a.pdf", b"Not a real PDF file"),
+        ("b.pdf", b"Also not a real PDF file"),
+    ]);
+
+    let req = test::TestRequest::post()
+        .uri("/extract_xml_batch")
+        .insert_header(("content-type", content_type))
+        .set_payload(payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: Value = test::read_body_json(resp).await;
+    let results = body["results"].as_array().expect("results array");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["filename"], "a.pdf");
+    assert_eq!(results[1]["filename"], "b.pdf");
+    assert!(results[0]["file status"]
+        .as_str()
+        .unwrap()
+        .contains("Not a valid PDF file"));
+}
+
+#[actix_web::test]
+async fn test_extract_xml_download_mode_returns_xml_attachment() {
+    let app = test::init_service(create_test_app()).await;
+
+    let pdf_bytes = build_valid_pdfa3_with_xml("factur-x.xml", "<xml>invoice</xml>");
+    let (payload, headers) = pdf_upload_request("invoice.pdf", pdf_bytes);
+
+    let mut req = test::TestRequest::post().uri("/extract_xml?download=true");
+    for (name, value) in headers.iter() {
+        req = req.insert_header((name.clone(), value.clone()));
+    }
+    let req = req.set_payload(payload).to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("content-type").and_then(|v| v.to_str().ok()),
+        Some("application/xml")
+    );
+
+    let disposition = resp
+        .headers()
+        .get("content-disposition")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    assert!(disposition.contains("attachment"));
+    assert!(disposition.contains("factur-x.xml"));
+
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Bytes::from_static(b"<xml>invoice</xml>"));
 }
 
 // Unit tests for core functionality
@@ -161,7 +334,7 @@ async fn test_pdf_error_display() {
 #[tokio::test]
 async fn test_erechnung_service_with_invalid_data() {
     let invalid_data = vec![0x00, 0x01, 0x02, 0x03]; // Not a PDF
-    let result = ERechnungService::process_pdf(invalid_data);
+    let result = ERechnungService::process_pdf(invalid_data, false, ExtractOptions::default());
     
     assert!(result.is_err());
     let error = result.unwrap_err();
@@ -187,6 +360,8 @@ async fn test_success_response_serialization() {
         embedded_files: "factur-x.xml".to_string(),
         xml_content: "<xml>test</xml>".to_string(),
         xml_filename: "factur-x.xml".to_string(),
+        recovered: false,
+        invoice_data: None,
     };
     
     let json = serde_json::to_string(&success).unwrap();